@@ -1,4 +1,6 @@
+use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
+use std::time::Duration;
 
 use super::{
     message::Message,
@@ -6,7 +8,8 @@ use super::{
     response::{ErrorResponse, Response},
 };
 use crate::completion::response::StreamResponse;
-use futures::StreamExt;
+use serde::de::DeserializeOwned;
+use futures::{Stream, StreamExt};
 use reqwest::header;
 use reqwest_eventsource::{Event, EventSource};
 
@@ -18,17 +21,218 @@ pub enum CompletionOption {
     Stream(Vec<StreamResponse>),
 }
 
+/// A single tool handler: receives the model's tool-call arguments already parsed as JSON and
+/// returns the string content that should be fed back as a `Message::ToolMessage`.
+pub type ToolHandler = Box<dyn Fn(serde_json::Value) -> anyhow::Result<String>>;
+
+/// Maps a `Function::name` to the closure that fulfils it.
+///
+/// Register one handler per function declared in the request's `tools`, keyed by the exact
+/// name the model will call. Used by [`Groq::create_with_tools`] to dispatch each round's
+/// `tool_calls`.
+#[derive(Default)]
+pub struct ToolRegistry {
+    handlers: HashMap<String, ToolHandler>,
+}
+
+impl ToolRegistry {
+    pub fn new() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Registers `handler` under `name`, returning `self` for chaining.
+    pub fn register<F>(mut self, name: &str, handler: F) -> Self
+    where
+        F: Fn(serde_json::Value) -> anyhow::Result<String> + 'static,
+    {
+        self.handlers.insert(name.to_string(), Box::new(handler));
+        self
+    }
+
+    fn dispatch(&self, name: &str, args: serde_json::Value) -> anyhow::Result<String> {
+        let handler = self
+            .handlers
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("no registered tool handler for function '{}'", name))?;
+        handler(args)
+    }
+}
+
 /// # Private Fields
 /// - api_key, the API key used to authenticate with groq,
 /// - client, the reqwest::Client with built in connection pool,
 /// - tmp_messages, messages that stay there for only a single request. After the request they are cleared.
 /// - messages,  a Vec for containing messages send to the groq completion endpoint (historic messages will not clear after request)
+/// - endpoint, the chat-completions URL to POST against; defaults to groq but can target any OpenAI-compatible server.
 #[derive(Debug, Clone)]
 pub struct Groq {
     api_key: String,
     messages: Vec<Message>,
     tmp_messages: Vec<Message>,
     client: reqwest::Client,
+    endpoint: String,
+    retry: RetryPolicy,
+    context_limit: Option<usize>,
+    reserved_completion_tokens: usize,
+}
+
+/// The groq chat-completions endpoint used by default.
+const GROQ_ENDPOINT: &str = "https://api.groq.com/openai/v1/chat/completions";
+
+/// Controls how transient failures (HTTP 429 and 5xx, timeouts, connection resets) are retried.
+///
+/// `max_attempts` counts the first try, so `1` disables retrying. Successive waits grow
+/// exponentially from `base_backoff`; a server-provided `Retry-After` header overrides the
+/// computed backoff when present.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            base_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Exponential backoff for the given 1-based attempt number.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.base_backoff * 2u32.saturating_pow(attempt.saturating_sub(1))
+    }
+}
+
+/// Returns `true` for status codes worth retrying on an idempotent request.
+fn is_transient_status(status: reqwest::StatusCode) -> bool {
+    status == reqwest::StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Parses a `Retry-After` header in either the delay-seconds or HTTP-date form.
+fn retry_after(resp: &reqwest::Response) -> Option<Duration> {
+    let value = resp.headers().get(header::RETRY_AFTER)?.to_str().ok()?;
+    parse_retry_after(value, std::time::SystemTime::now())
+}
+
+/// Parses a `Retry-After` value relative to `now`, returning the delay to wait.
+///
+/// Accepts both the delay-seconds form (`120`) and the HTTP-date form
+/// (`Wed, 21 Oct 2015 07:28:00 GMT`); a date at or before `now` yields `None`.
+fn parse_retry_after(value: &str, now: std::time::SystemTime) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let when = httpdate::parse_http_date(value).ok()?;
+    when.duration_since(now).ok().filter(|wait| !wait.is_zero())
+}
+
+/// Returns `true` for `EventSource` errors worth retrying the handshake on: transient transport
+/// failures and transient HTTP status codes. Permanent errors (e.g. 400/401) are not retried.
+fn is_transient_eventsource_error(err: &reqwest_eventsource::Error) -> bool {
+    use reqwest_eventsource::Error;
+    match err {
+        Error::Transport(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+        Error::InvalidStatusCode(status, _) => is_transient_status(*status),
+        _ => false,
+    }
+}
+
+/// Builder for a tuned [`Groq`] client: proxy, timeouts, default headers, and a retry policy.
+#[derive(Debug, Clone)]
+pub struct GroqBuilder {
+    api_key: String,
+    endpoint: String,
+    default_headers: Option<header::HeaderMap>,
+    proxy: Option<String>,
+    timeout: Option<Duration>,
+    connect_timeout: Option<Duration>,
+    retry: RetryPolicy,
+}
+
+impl GroqBuilder {
+    fn new(api_key: &str) -> Self {
+        Self {
+            api_key: api_key.into(),
+            endpoint: GROQ_ENDPOINT.into(),
+            default_headers: None,
+            proxy: None,
+            timeout: None,
+            connect_timeout: None,
+            retry: RetryPolicy::default(),
+        }
+    }
+
+    /// Targets an OpenAI-compatible chat-completions endpoint other than groq.
+    pub fn endpoint(mut self, url: &str) -> Self {
+        self.endpoint = url.into();
+        self
+    }
+
+    /// Applies a set of default headers to every request.
+    pub fn default_headers(mut self, headers: header::HeaderMap) -> Self {
+        self.default_headers = Some(headers);
+        self
+    }
+
+    /// Routes requests through the given proxy URL.
+    pub fn proxy(mut self, url: &str) -> Self {
+        self.proxy = Some(url.into());
+        self
+    }
+
+    /// Sets the total request timeout.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the connection-establishment timeout.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets the retry policy used for transient failures.
+    pub fn retry(mut self, max_attempts: u32, base_backoff: Duration) -> Self {
+        self.retry = RetryPolicy {
+            max_attempts,
+            base_backoff,
+        };
+        self
+    }
+
+    /// Builds the underlying `reqwest::Client` from the configured settings.
+    pub fn build(self) -> anyhow::Result<Groq> {
+        let mut client = reqwest::Client::builder();
+        if let Some(headers) = self.default_headers {
+            client = client.default_headers(headers);
+        }
+        if let Some(proxy) = self.proxy {
+            client = client.proxy(reqwest::Proxy::all(proxy)?);
+        }
+        if let Some(timeout) = self.timeout {
+            client = client.timeout(timeout);
+        }
+        if let Some(connect_timeout) = self.connect_timeout {
+            client = client.connect_timeout(connect_timeout);
+        }
+        Ok(Groq {
+            api_key: self.api_key,
+            client: client.build()?,
+            tmp_messages: Vec::new(),
+            messages: Vec::new(),
+            endpoint: self.endpoint,
+            retry: self.retry,
+            context_limit: None,
+            reserved_completion_tokens: 0,
+        })
+    }
 }
 
 impl Groq {
@@ -46,9 +250,48 @@ impl Groq {
             client: reqwest::Client::new(),
             tmp_messages: Vec::new(),
             messages: Vec::new(),
+            endpoint: GROQ_ENDPOINT.into(),
+            retry: RetryPolicy::default(),
+            context_limit: None,
+            reserved_completion_tokens: 0,
         }
     }
 
+    /// Returns a [`GroqBuilder`] for configuring proxy, timeouts, default headers, and the
+    /// retry policy before constructing the client.
+    pub fn builder(api_key: &str) -> GroqBuilder {
+        GroqBuilder::new(api_key)
+    }
+
+    /// Returns a Groq client pointed at an arbitrary OpenAI-compatible chat-completions
+    /// endpoint, with an optional set of default headers applied to every request.
+    ///
+    /// Since the request/response schema is the OpenAI chat-completions format, this lets the
+    /// same client target local inference servers, OpenAI itself, or proxies. `base_url` is
+    /// the full completions URL to POST against.
+    pub fn with_config(
+        api_key: &str,
+        base_url: &str,
+        default_headers: Option<header::HeaderMap>,
+    ) -> anyhow::Result<Self> {
+        let client = match default_headers {
+            Some(headers) => reqwest::Client::builder()
+                .default_headers(headers)
+                .build()?,
+            None => reqwest::Client::new(),
+        };
+        Ok(Self {
+            api_key: api_key.into(),
+            client,
+            tmp_messages: Vec::new(),
+            messages: Vec::new(),
+            endpoint: base_url.into(),
+            retry: RetryPolicy::default(),
+            context_limit: None,
+            reserved_completion_tokens: 0,
+        })
+    }
+
     pub fn add_message(mut self, msg: Message) -> Self {
         //! Adds a message to the internal message vector
         self.messages.push(msg);
@@ -94,20 +337,109 @@ impl Groq {
         }
     }
 
-    /// Outputs the request messages that should be passed onto the request.
+    /// Outputs the raw assembled request messages (`tmp_messages` followed by `messages`).
     /// Utility function created for easier logic internally.
+    /// No trimming is applied here; context-window fitting happens in
+    /// [`Groq::get_request_messages_with_tmp_clear`] on the request-building path.
     fn get_all_request_messages(&self) -> Vec<Message> {
         if self.tmp_messages.is_empty() {
             self.messages.clone()
         } else {
-            return vec![self.tmp_messages.clone(), self.messages.clone()].concat();
+            vec![self.tmp_messages.clone(), self.messages.clone()].concat()
         }
     }
 
+    /// Estimates the token count of a single message by tokenizing its serialized form, with a
+    /// small fixed overhead for the role/structure wrapper.
+    fn message_tokens<F: Fn(&str) -> usize>(msg: &Message, tokenize: &F) -> usize {
+        let body = serde_json::to_string(msg).unwrap_or_default();
+        tokenize(&body) + 4
+    }
+
+    /// Default token estimate: roughly one token per four characters, matching the common
+    /// BPE heuristic when no tokenizer is plugged in.
+    fn estimate_tokens(text: &str) -> usize {
+        text.chars().count().div_ceil(4)
+    }
+
+    /// Estimates the token count of the raw assembled request messages using the default
+    /// heuristic. Reports the pre-trim size so callers can observe when they are over budget.
+    pub fn count_tokens(&self) -> usize {
+        self.count_tokens_with(Self::estimate_tokens)
+    }
+
+    /// Estimates the token count of the raw assembled request messages using a caller-supplied
+    /// `tokenize` function, e.g. an embedded BPE tokenizer. Counts the pre-trim list so usage
+    /// can exceed `context_limit`.
+    pub fn count_tokens_with<F: Fn(&str) -> usize>(&self, tokenize: F) -> usize {
+        self.get_all_request_messages()
+            .iter()
+            .map(|msg| Self::message_tokens(msg, &tokenize))
+            .sum()
+    }
+
+    /// Enables automatic context-window trimming for a model whose maximum context is
+    /// `model_max_tokens`. Combine with [`Groq::with_reserved_completion_tokens`] to leave room
+    /// for the completion.
+    pub fn with_context_limit(mut self, model_max_tokens: usize) -> Self {
+        self.context_limit = Some(model_max_tokens);
+        self
+    }
+
+    /// Reserves `tokens` of the context window for the completion, so trimming keeps the prompt
+    /// within `context_limit - tokens`. Pass the request's `max_tokens` here.
+    pub fn with_reserved_completion_tokens(mut self, tokens: usize) -> Self {
+        self.reserved_completion_tokens = tokens;
+        self
+    }
+
+    /// Drops the oldest non-system messages until the list fits the configured context budget,
+    /// measuring with `tokenize`. System messages are always retained; with no limit set the
+    /// list is returned unchanged.
+    ///
+    /// Trimming happens in tool-call-aware units: an assistant turn carrying `tool_calls` is
+    /// dropped together with the `ToolMessage`s that answer it, so the request never ends up
+    /// with an orphaned tool result (or a tool-call without its results), which the API rejects.
+    fn fit_to_context<F: Fn(&str) -> usize>(&self, mut msgs: Vec<Message>, tokenize: &F) -> Vec<Message> {
+        let Some(limit) = self.context_limit else {
+            return msgs;
+        };
+        let budget = limit.saturating_sub(self.reserved_completion_tokens);
+        while msgs
+            .iter()
+            .map(|msg| Self::message_tokens(msg, tokenize))
+            .sum::<usize>()
+            > budget
+        {
+            let Some(idx) = msgs
+                .iter()
+                .position(|m| !matches!(m, Message::SystemMessage { .. }))
+            else {
+                // Only system messages remain; nothing more can be dropped.
+                break;
+            };
+            let mut end = idx + 1;
+            // An assistant turn with tool_calls owns the ToolMessages that immediately follow it.
+            if matches!(
+                &msgs[idx],
+                Message::AssistantMessage { tool_calls: Some(calls), .. } if !calls.is_empty()
+            ) {
+                while end < msgs.len() && matches!(msgs[end], Message::ToolMessage { .. }) {
+                    end += 1;
+                }
+            }
+            msgs.drain(idx..end);
+        }
+        msgs
+    }
+
     /// Outputs the request messages that should be passed onto the request and clears the tmp messages.
     /// Utility function created for easier logic internally.
+    /// When a context limit is configured the assembled list is trimmed to fit; automatic
+    /// trimming always measures with the built-in estimate ([`Groq::count_tokens`]), not a
+    /// tokenizer passed to [`Groq::count_tokens_with`].
     fn get_request_messages_with_tmp_clear(&mut self) -> Vec<Message> {
-        let all = self.get_all_request_messages();
+        let all = self.fit_to_context(self.get_all_request_messages(), &Self::estimate_tokens);
         self.clear_tmp_messages_override();
         return all;
     }
@@ -127,13 +459,46 @@ impl Groq {
             req.is_stream(),
             "'create_stream_completion' func must have the stream flag turned on in request body"
         );
-        let mut stream = EventSource::new(
-            self.client
-                .post("https://api.groq.com/openai/v1/chat/completions")
-                .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
-                .header(header::ACCEPT, "text/event-stream")
-                .json(&req),
-        )?;
+        // Establish the SSE connection, retrying the initial handshake on transient failures.
+        let mut attempt = 0;
+        let mut stream = loop {
+            attempt += 1;
+            let mut stream = EventSource::new(
+                self.client
+                    .post(&self.endpoint)
+                    .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
+                    .header(header::ACCEPT, "text/event-stream")
+                    .json(&req),
+            )?;
+            match stream.next().await {
+                Some(Ok(Event::Open)) => {
+                    println!("Connection Open!");
+                    break stream;
+                }
+                // The first event was data already; no handshake retry needed.
+                Some(Ok(Event::Message(_))) => {
+                    anyhow::bail!("stream produced data before the open event");
+                }
+                Some(Err(err))
+                    if attempt < self.retry.max_attempts
+                        && is_transient_eventsource_error(&err) =>
+                {
+                    stream.close();
+                    let wait = match &err {
+                        reqwest_eventsource::Error::InvalidStatusCode(_, resp) => retry_after(resp),
+                        _ => None,
+                    }
+                    .unwrap_or_else(|| self.retry.backoff(attempt));
+                    tokio::time::sleep(wait).await;
+                    continue;
+                }
+                Some(Err(err)) => {
+                    stream.close();
+                    anyhow::bail!("Error: {}", err);
+                }
+                None => anyhow::bail!("stream closed before the open event"),
+            }
+        };
         let mut bufs: Vec<StreamResponse> = Vec::new();
         while let Some(event) = stream.next().await {
             match event {
@@ -155,6 +520,55 @@ impl Groq {
         Ok(CompletionOption::Stream(bufs))
     }
 
+    /// Opens a streaming completion and yields each `StreamResponse` live as it arrives.
+    ///
+    /// Unlike [`Groq::create`] with the stream flag — which buffers every SSE chunk into a
+    /// `Vec` and only returns after `[DONE]` — this returns an `impl Stream` so callers can
+    /// render tokens incrementally. `[DONE]` terminates the stream, a
+    /// `reqwest_eventsource::Error` is surfaced as the item error, and the underlying
+    /// `EventSource` is closed when the stream is dropped.
+    pub fn create_stream_with_handler(
+        &mut self,
+        req: request::builder::RequestBuilder,
+    ) -> anyhow::Result<impl Stream<Item = anyhow::Result<StreamResponse>>> {
+        let req = req
+            .with_messages(self.get_request_messages_with_tmp_clear())?
+            .build();
+        anyhow::ensure!(
+            req.is_stream(),
+            "'create_stream_with_handler' func must have the stream flag turned on in request body"
+        );
+        let stream = EventSource::new(
+            self.client
+                .post(&self.endpoint)
+                .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
+                .header(header::ACCEPT, "text/event-stream")
+                .json(&req),
+        )?;
+        Ok(futures::stream::unfold(Some(stream), |state| async move {
+            let mut stream = state?;
+            loop {
+                match stream.next().await {
+                    Some(Ok(Event::Open)) => continue,
+                    Some(Ok(Event::Message(message))) => {
+                        if message.data == "[DONE]" {
+                            stream.close();
+                            return None;
+                        }
+                        let item = serde_json::from_str(&message.data).map_err(anyhow::Error::from);
+                        return Some((item, Some(stream)));
+                    }
+                    // Surface the error as the final item, then end the stream.
+                    Some(Err(err)) => {
+                        stream.close();
+                        return Some((Err(anyhow::anyhow!("Error: {}", err)), None));
+                    }
+                    None => return None,
+                }
+            }
+        }))
+    }
+
     async fn create_non_stream_completion(
         &mut self,
         req: request::builder::RequestBuilder,
@@ -162,16 +576,36 @@ impl Groq {
         let req = req
             .with_messages(self.get_request_messages_with_tmp_clear())?
             .build();
-        let body = (self.client)
-            .post("https://api.groq.com/openai/v1/chat/completions")
-            .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
-            .json(&req)
-            .send()
-            .await?;
-        if body.status() == reqwest::StatusCode::OK {
-            Ok(CompletionOption::NonStream(body.json::<Response>().await?))
-        } else {
-            let statcode = body.status().clone();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let body = (self.client)
+                .post(&self.endpoint)
+                .header(header::AUTHORIZATION, format!("Bearer {}", self.api_key))
+                .json(&req)
+                .send()
+                .await;
+            let body = match body {
+                Ok(body) => body,
+                // Retry idempotent transient transport failures (timeouts, dropped connections).
+                Err(err)
+                    if attempt < self.retry.max_attempts
+                        && (err.is_timeout() || err.is_connect() || err.is_request()) =>
+                {
+                    tokio::time::sleep(self.retry.backoff(attempt)).await;
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+            if body.status() == reqwest::StatusCode::OK {
+                return Ok(CompletionOption::NonStream(body.json::<Response>().await?));
+            }
+            let statcode = body.status();
+            if is_transient_status(statcode) && attempt < self.retry.max_attempts {
+                let wait = retry_after(&body).unwrap_or_else(|| self.retry.backoff(attempt));
+                tokio::time::sleep(wait).await;
+                continue;
+            }
             let mut error: ErrorResponse = serde_json::from_str(&body.text().await?)?;
             error.code = statcode;
             anyhow::bail!(error)
@@ -188,6 +622,115 @@ impl Groq {
             self.create_stream_completion(req).await
         }
     }
+
+    /// Sends a non-stream request and deserializes the assistant's content into `T`.
+    ///
+    /// Pair this with a `json_object` or `json_schema` response format (see
+    /// [`ResponseFormat`](request::ResponseFormat)) so the model is constrained to emit valid
+    /// JSON, then receive it as a typed value instead of hand-parsing free-form text.
+    pub async fn create_structured<T: DeserializeOwned>(
+        &mut self,
+        req: request::builder::RequestBuilder,
+    ) -> anyhow::Result<T> {
+        anyhow::ensure!(
+            !req.is_stream(),
+            "'create_structured' cannot be used with the stream flag turned on"
+        );
+        let resp = match self.create_non_stream_completion(req).await? {
+            CompletionOption::NonStream(resp) => resp,
+            CompletionOption::Stream(_) => {
+                anyhow::bail!("'create_structured' cannot be used with the stream flag turned on")
+            }
+        };
+        let content = resp
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.as_deref())
+            .ok_or_else(|| anyhow::anyhow!("completion response contained no assistant content"))?;
+        Ok(serde_json::from_str(content)?)
+    }
+
+    /// Drives a multi-step tool-calling conversation to completion.
+    ///
+    /// Each round sends the accumulated messages, inspects the assistant reply's `tool_calls`,
+    /// dispatches every call to its handler in `registry`, appends the results as
+    /// `Message::ToolMessage`s (immediately after the assistant message that requested them so
+    /// ordering is preserved), and re-sends. The loop returns the final `Response` once the
+    /// model answers with no further `tool_calls`; if the model is still calling tools after
+    /// `max_rounds` rounds, an error is returned instead.
+    ///
+    /// The handler closures are registered by the caller keyed to the `Function::name` they
+    /// declared in the request's `tools`; calling an unregistered function is an error.
+    pub async fn create_with_tools(
+        &mut self,
+        req: request::builder::RequestBuilder,
+        registry: &ToolRegistry,
+        max_rounds: usize,
+    ) -> anyhow::Result<Response> {
+        anyhow::ensure!(max_rounds > 0, "'max_rounds' must be at least 1");
+        anyhow::ensure!(
+            !req.is_stream(),
+            "'create_with_tools' cannot be used with the stream flag turned on"
+        );
+
+        // Promote any one-shot tmp prompt to permanent history up front: otherwise the first
+        // round's `get_request_messages_with_tmp_clear` would clear it, and rounds >= 2 would
+        // re-send the tool results without the original question.
+        let tmp = std::mem::take(&mut self.tmp_messages);
+        self.messages.extend(tmp);
+
+        for _ in 0..max_rounds {
+            let resp = match self.create_non_stream_completion(req.clone()).await? {
+                CompletionOption::NonStream(resp) => resp,
+                CompletionOption::Stream(_) => {
+                    anyhow::bail!("expected a non-stream response from the completion endpoint")
+                }
+            };
+
+            let choice = resp
+                .choices
+                .first()
+                .ok_or_else(|| anyhow::anyhow!("completion response contained no choices"))?;
+            let tool_calls = match &choice.message.tool_calls {
+                Some(calls) if !calls.is_empty() => calls,
+                // `tool_choice: "none"` or a plain answer: record the final assistant turn so
+                // history stays symmetric with the intermediate turns, then return.
+                _ => {
+                    self.messages.push(Message::AssistantMessage {
+                        role: choice.message.role.clone(),
+                        content: choice.message.content.clone(),
+                        name: None,
+                        tool_call_id: None,
+                        tool_calls: None,
+                    });
+                    return Ok(resp);
+                }
+            };
+
+            // Record the assistant turn before its tool results so the next request keeps the
+            // `assistant(tool_calls) -> tool -> tool` ordering the API requires.
+            self.messages.push(Message::AssistantMessage {
+                role: choice.message.role.clone(),
+                content: choice.message.content.clone(),
+                name: None,
+                tool_call_id: None,
+                tool_calls: Some(tool_calls.clone()),
+            });
+
+            for call in tool_calls {
+                let args: serde_json::Value = serde_json::from_str(&call.function.arguments)?;
+                let content = registry.dispatch(&call.function.name, args)?;
+                self.messages.push(Message::ToolMessage {
+                    role: Some("tool".to_string()),
+                    content: Some(content),
+                    name: Some(call.function.name.clone()),
+                    tool_call_id: Some(call.id.clone()),
+                });
+            }
+        }
+
+        anyhow::bail!("tool-calling loop exceeded the {} round guard", max_rounds)
+    }
 }
 
 impl Hash for Groq {
@@ -318,4 +861,105 @@ mod completion_test {
         assert!(client.get_tmp_request_messages().is_none());
         Ok(())
     }
+
+    #[test]
+    fn retry_policy_backoff_is_exponential() {
+        let policy = super::RetryPolicy {
+            max_attempts: 5,
+            base_backoff: std::time::Duration::from_millis(100),
+        };
+        assert_eq!(policy.backoff(1), std::time::Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), std::time::Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), std::time::Duration::from_millis(400));
+    }
+
+    #[test]
+    fn transient_status_gating() {
+        use reqwest::StatusCode;
+        assert!(super::is_transient_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(super::is_transient_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(super::is_transient_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(!super::is_transient_status(StatusCode::BAD_REQUEST));
+        assert!(!super::is_transient_status(StatusCode::UNAUTHORIZED));
+        assert!(!super::is_transient_status(StatusCode::OK));
+    }
+
+    #[test]
+    fn retry_after_parses_seconds_and_http_date() {
+        use std::time::{Duration, SystemTime};
+        let now = SystemTime::UNIX_EPOCH;
+        assert_eq!(
+            super::parse_retry_after("120", now),
+            Some(Duration::from_secs(120))
+        );
+        let date = httpdate::fmt_http_date(now + Duration::from_secs(60));
+        assert_eq!(
+            super::parse_retry_after(&date, now),
+            Some(Duration::from_secs(60))
+        );
+        // A date at or before `now`, and unparseable values, yield no delay.
+        assert_eq!(
+            super::parse_retry_after(&httpdate::fmt_http_date(now), now + Duration::from_secs(60)),
+            None
+        );
+        assert_eq!(super::parse_retry_after("not-a-date", now), None);
+    }
+
+    #[test]
+    fn tool_registry_dispatch_and_missing_handler() {
+        let registry = super::ToolRegistry::new().register("echo", |args| {
+            Ok(args
+                .get("msg")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string())
+        });
+        let out = registry
+            .dispatch("echo", serde_json::json!({ "msg": "hi" }))
+            .unwrap();
+        assert_eq!(out, "hi");
+        assert!(registry
+            .dispatch("missing", serde_json::json!({}))
+            .is_err());
+    }
+
+    #[test]
+    fn fit_to_context_keeps_system_and_drops_oldest() {
+        let system = Message::SystemMessage {
+            role: Some("system".to_string()),
+            content: Some("sys".to_string()),
+            name: None,
+            tool_call_id: None,
+        };
+        let user = |body: &str| Message::UserMessage {
+            role: Some("user".to_string()),
+            content: Some(body.to_string()),
+            name: None,
+            tool_call_id: None,
+        };
+        let msgs = vec![
+            system,
+            user("oldest"),
+            user("middle"),
+            user("newest"),
+        ];
+        // Fixed 10 tokens per message (+4 overhead) keeps the budget arithmetic independent of
+        // the serialized wire format: budget 40 fits the system message plus one more.
+        let tokenize = |_s: &str| 10usize;
+        let client = Groq::new("k")
+            .with_context_limit(40)
+            .with_reserved_completion_tokens(0);
+        let trimmed = client.fit_to_context(msgs, &tokenize);
+
+        assert_eq!(trimmed.len(), 2);
+        assert!(matches!(trimmed[0], Message::SystemMessage { .. }));
+        assert!(trimmed.iter().any(|m| matches!(
+            m,
+            Message::UserMessage { content: Some(c), .. } if c == "newest"
+        )));
+        assert!(!trimmed.iter().any(|m| matches!(
+            m,
+            Message::UserMessage { content: Some(c), .. } if c == "oldest"
+        )));
+    }
 }
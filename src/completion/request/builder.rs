@@ -0,0 +1,95 @@
+use super::{Request, ResponseFormat, StopEnum};
+use crate::completion::message::Message;
+
+/// Fluent builder for a [`Request`].
+///
+/// Every setter consumes and returns `self` so calls can be chained; [`RequestBuilder::build`]
+/// finalizes the `Request` that is sent to the completion endpoint.
+#[derive(Debug, Clone)]
+pub struct RequestBuilder {
+    req: Request,
+}
+
+impl RequestBuilder {
+    /// Creates a builder for `model` seeded with `messages`.
+    ///
+    /// Returns `None` when `messages` is empty, since the completion endpoint requires at least
+    /// one message; callers typically `.context(..)?` the result.
+    pub fn new(model: String, messages: Vec<Message>) -> Option<Self> {
+        if messages.is_empty() {
+            return None;
+        }
+        Some(Self {
+            req: Request {
+                logit_bias: None,
+                logprobs: false,
+                frequency_penalty: 0.0,
+                max_tokens: None,
+                messages,
+                model,
+                n: 1,
+                presence_penalty: 0.0,
+                response_format: ResponseFormat::Text,
+                seed: None,
+                stop: None,
+                stream: false,
+                temperature: 1.0,
+                tool_choice: None,
+                tools: None,
+                top_logprobs: None,
+                top_p: 1.0,
+                user: None,
+            },
+        })
+    }
+
+    /// Replaces the request's messages, erroring when the list is empty.
+    pub fn with_messages(mut self, messages: Vec<Message>) -> anyhow::Result<Self> {
+        anyhow::ensure!(!messages.is_empty(), "the messages vec must contain at least 1 Message");
+        self.req.messages = messages;
+        Ok(self)
+    }
+
+    /// Toggles server-sent-event streaming.
+    pub fn with_stream(mut self, stream: bool) -> Self {
+        self.req.stream = stream;
+        self
+    }
+
+    /// Sets a single stop token.
+    pub fn with_stop(mut self, stop: &str) -> Self {
+        self.req.stop = Some(StopEnum::Token(stop.to_string()));
+        self
+    }
+
+    /// Sets a list of stop tokens.
+    pub fn with_stops(mut self, stops: Vec<String>) -> Self {
+        self.req.stop = Some(StopEnum::Tokens(stops));
+        self
+    }
+
+    /// Sets the response format directly.
+    pub fn with_response_format(mut self, response_format: ResponseFormat) -> Self {
+        self.req.response_format = response_format;
+        self
+    }
+
+    /// Requests JSON mode, constraining the model to emit a valid `json_object`.
+    pub fn with_json_mode(self) -> Self {
+        self.with_response_format(ResponseFormat::JsonObject)
+    }
+
+    /// Requests schema-constrained output, named `name` and validated against `schema`.
+    pub fn with_json_schema(self, name: &str, schema: serde_json::Value, strict: bool) -> Self {
+        self.with_response_format(ResponseFormat::json_schema(name, schema, strict))
+    }
+
+    pub fn is_stream(&self) -> bool {
+        self.req.is_stream()
+    }
+
+    /// Finalizes the builder into the `Request` to be sent.
+    pub fn build(self) -> Request {
+        self.req
+    }
+}
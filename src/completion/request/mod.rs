@@ -2,7 +2,7 @@ use super::message::Message;
 use serde::Serialize;
 pub mod builder;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Request {
     // unused for openai integration only
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -51,38 +51,61 @@ impl Request {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum ToolChoiceEnum {
     Str(String),
     Tool(Tool),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum StopEnum {
     Token(String),
     Tokens(Vec<String>),
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Tool {
     #[serde(rename(serialize = "type"))]
     pub tool_type: String,
     pub function: Function,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Function {
     pub description: Option<String>,
     pub name: Option<String>,
     pub parameters: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ResponseFormat {
-    #[serde(rename(serialize = "type"))]
-    pub response_type: String,
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    Text,
+    JsonObject,
+    JsonSchema { json_schema: JsonSchemaFormat },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JsonSchemaFormat {
+    pub name: String,
+    pub schema: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+}
+
+impl ResponseFormat {
+    /// Builds a `json_schema` response format from a schema value, its name, and the strict flag.
+    pub fn json_schema(name: &str, schema: serde_json::Value, strict: bool) -> Self {
+        ResponseFormat::JsonSchema {
+            json_schema: JsonSchemaFormat {
+                name: name.to_string(),
+                schema,
+                strict: Some(strict),
+            },
+        }
+    }
 }
 
 #[cfg(test)]
@@ -107,9 +130,7 @@ mod request_test {
             model: "".into(),
             n: 1,
             presence_penalty: 0.0,
-            response_format: ResponseFormat {
-                response_type: "text".into(),
-            },
+            response_format: ResponseFormat::Text,
             seed: None,
             stop: None,
             stream: false,
@@ -148,9 +169,7 @@ mod request_test {
             model: "".into(),
             n: 1,
             presence_penalty: 0.0,
-            response_format: ResponseFormat {
-                response_type: "text".into(),
-            },
+            response_format: ResponseFormat::Text,
             seed: None,
             stop: Some(StopEnum::Token("endline".into())),
             stream: false,
@@ -218,9 +237,7 @@ mod request_test {
             model: "".into(),
             n: 1,
             presence_penalty: 0.0,
-            response_format: ResponseFormat {
-                response_type: "text".into(),
-            },
+            response_format: ResponseFormat::Text,
             seed: None,
             stop: None,
             stream: false,
@@ -240,4 +257,21 @@ mod request_test {
         assert_eq!(target_json, out_json);
         Ok(())
     }
+
+    #[test]
+    fn response_format_serialization() {
+        assert_eq!(
+            serde_json::to_string(&ResponseFormat::Text).unwrap(),
+            r#"{"type":"text"}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&ResponseFormat::JsonObject).unwrap(),
+            r#"{"type":"json_object"}"#
+        );
+        let rf = ResponseFormat::json_schema("my_schema", serde_json::json!({"type": "object"}), true);
+        assert_eq!(
+            serde_json::to_string(&rf).unwrap(),
+            r#"{"type":"json_schema","json_schema":{"name":"my_schema","schema":{"type":"object"},"strict":true}}"#
+        );
+    }
 }